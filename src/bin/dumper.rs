@@ -16,11 +16,7 @@ pub fn main() {
 
     let file = File::open(args.path).unwrap();
     let library = Library::new(&file).unwrap();
-    for asset in library.assets() {
-        println!(
-            "Found Asset: [Id: {:#x}, Size: {}]",
-            asset.id.raw(),
-            asset.size
-        );
+    for path in library.assets() {
+        println!("Found Asset: [Path: {}]", path);
     }
 }
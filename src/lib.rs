@@ -1,13 +1,14 @@
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use memmap::Mmap;
-use std::collections::hash_map::{self, DefaultHasher};
-use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::hash::{Hash, Hasher};
 use std::io::Read;
 use std::io::Write;
+use std::io::{Seek, SeekFrom};
 use std::ops::Deref;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::{fs, io};
 
 fn align_up(val: u64, align: u64) -> u64 {
@@ -16,25 +17,49 @@ fn align_up(val: u64, align: u64) -> u64 {
 
 const ASSET_ALIGN_SIZE: u64 = 64;
 
+// Upper bound used when pre-sizing a Vec from a count that comes straight off
+// the wire (e.g. `num_assets`/`num_names` read from a stream). Large enough
+// that real libraries never reallocate during parsing, small enough that a
+// corrupt or malicious count near u64::MAX can't trigger a capacity-overflow
+// panic or an oversized up-front allocation; elements beyond this are still
+// read correctly, just via normal amortized Vec growth instead of one shot.
+const MAX_PREALLOCATED_ELEMENTS: u64 = 1 << 20;
+
+fn bounded_capacity(count: u64) -> usize {
+    std::cmp::min(count, MAX_PREALLOCATED_ELEMENTS) as usize
+}
+
+// Bumped whenever the on-disk layout changes in a way older readers can't
+// cope with (e.g. new fields on AssetTableEntry, or a new trailing section
+// like the dependency table). Library::new rejects files from a newer
+// version rather than misinterpreting their bytes.
+const CURRENT_FORMAT_VERSION: u32 = 3;
+
 #[derive(Debug)]
 struct FileHeader {
     magic_number: u64,
+    version: u32,
 }
 
 impl FileHeader {
     fn from_stream<T: Read>(stream: &mut T) -> Result<Self, io::Error> {
         let magic_number = stream.read_u64::<LittleEndian>()?;
+        let version = stream.read_u32::<LittleEndian>()?;
 
-        Ok(Self { magic_number })
+        Ok(Self {
+            magic_number,
+            version,
+        })
     }
 
     fn to_stream<T: Write>(&self, stream: &mut T) -> Result<(), io::Error> {
         stream.write_u64::<LittleEndian>(self.magic_number)?;
+        stream.write_u32::<LittleEndian>(self.version)?;
         Ok(())
     }
 
     fn get_serialized_size() -> usize {
-        8
+        12
     }
 }
 
@@ -60,11 +85,41 @@ impl AssetTableHeader {
     }
 }
 
+/// Compression codec an asset's bytes are stored with. `size` on
+/// `AssetTableEntry` is always the uncompressed length; `stored_size` is how
+/// many bytes are actually present on disk for the entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Codec {
+    None = 0,
+    Zstd = 1,
+    Lz4 = 2,
+}
+
+impl Codec {
+    fn from_u8(value: u8) -> Result<Self, io::Error> {
+        match value {
+            0 => Ok(Codec::None),
+            1 => Ok(Codec::Zstd),
+            2 => Ok(Codec::Lz4),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unrecognized codec byte: {}", value),
+            )),
+        }
+    }
+}
+
 #[derive(Debug)]
 struct AssetTableEntry {
     id: u64,
     offset: u64,
     size: u64,
+    // Index into the name table of the path this asset was built from.
+    name_index: u32,
+    // Number of bytes actually stored on disk for this asset (== size when
+    // codec is Codec::None).
+    stored_size: u64,
+    codec: u8,
 }
 
 impl AssetTableEntry {
@@ -72,71 +127,235 @@ impl AssetTableEntry {
         let id = stream.read_u64::<LittleEndian>()?;
         let offset = stream.read_u64::<LittleEndian>()?;
         let size = stream.read_u64::<LittleEndian>()?;
-
-        Ok(Self { id, offset, size })
+        let name_index = stream.read_u32::<LittleEndian>()?;
+        let stored_size = stream.read_u64::<LittleEndian>()?;
+        let codec = stream.read_u8()?;
+
+        Ok(Self {
+            id,
+            offset,
+            size,
+            name_index,
+            stored_size,
+            codec,
+        })
     }
 
     fn to_stream<T: Write>(&self, stream: &mut T) -> Result<(), io::Error> {
         stream.write_u64::<LittleEndian>(self.id)?;
         stream.write_u64::<LittleEndian>(self.offset)?;
         stream.write_u64::<LittleEndian>(self.size)?;
+        stream.write_u32::<LittleEndian>(self.name_index)?;
+        stream.write_u64::<LittleEndian>(self.stored_size)?;
+        stream.write_u8(self.codec)?;
         Ok(())
     }
 
     fn get_serialized_size() -> usize {
-        24
+        37
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct AssetId(u64);
+#[derive(Debug)]
+struct NameTableHeader {
+    num_names: u64,
+    // Total byte size of the name data that follows this header, so readers
+    // can jump straight to whatever comes after the name table (the
+    // dependency table) without scanning every length-prefixed name.
+    data_size: u64,
+}
 
-impl AssetId {
-    fn from_str(str: &str) -> Self {
-        let mut hasher = DefaultHasher::new();
-        str.hash(&mut hasher);
-        Self(hasher.finish())
+impl NameTableHeader {
+    fn from_stream<T: Read>(stream: &mut T) -> Result<Self, io::Error> {
+        let num_names = stream.read_u64::<LittleEndian>()?;
+        let data_size = stream.read_u64::<LittleEndian>()?;
+
+        Ok(Self {
+            num_names,
+            data_size,
+        })
+    }
+
+    fn to_stream<T: Write>(&self, stream: &mut T) -> Result<(), io::Error> {
+        stream.write_u64::<LittleEndian>(self.num_names)?;
+        stream.write_u64::<LittleEndian>(self.data_size)?;
+        Ok(())
+    }
+
+    fn get_serialized_size() -> usize {
+        16
     }
 }
 
 #[derive(Debug, Default)]
-struct AssetTable {
-    entries: HashMap<AssetId, AssetTableEntry>,
+struct NameTable {
+    names: Vec<String>,
 }
 
-impl AssetTable {
+impl NameTable {
     fn from_stream<T: Read>(mut stream: &mut T) -> Result<Self, io::Error> {
-        let header = AssetTableHeader::from_stream(&mut stream)?;
-        let mut asset_table = AssetTable::default();
-        // TODO: Prevent infinite loops on num_assets and 32/64bit issues with offset/size
+        let header = NameTableHeader::from_stream(&mut stream)?;
+        let mut names = Vec::with_capacity(bounded_capacity(header.num_names));
+        for _ in 0..header.num_names {
+            let len = stream.read_u32::<LittleEndian>()? as usize;
+            let mut bytes = vec![0u8; len];
+            stream.read_exact(&mut bytes)?;
+            let name = String::from_utf8(bytes)
+                .map_err(|_| io::Error::from(io::ErrorKind::InvalidData))?;
+            names.push(name);
+        }
+
+        Ok(Self { names })
+    }
+
+    fn to_stream<T: Write>(&self, stream: &mut T) -> Result<(), io::Error> {
+        for name in &self.names {
+            let bytes = name.as_bytes();
+            stream.write_u32::<LittleEndian>(bytes.len() as u32)?;
+            stream.write_all(bytes)?;
+        }
+        Ok(())
+    }
+
+    fn get_serialized_size(&self) -> usize {
+        self.names.iter().map(|name| 4 + name.len()).sum()
+    }
+}
+
+#[derive(Debug)]
+struct DependencyTableHeader {
+    num_assets: u64,
+}
+
+impl DependencyTableHeader {
+    fn from_stream<T: Read>(stream: &mut T) -> Result<Self, io::Error> {
+        let num_assets = stream.read_u64::<LittleEndian>()?;
+
+        Ok(Self { num_assets })
+    }
+
+    fn to_stream<T: Write>(&self, stream: &mut T) -> Result<(), io::Error> {
+        stream.write_u64::<LittleEndian>(self.num_assets)?;
+        Ok(())
+    }
+
+    fn get_serialized_size() -> usize {
+        8
+    }
+}
+
+/// Per-asset lists of dependency `AssetId`s, stored in the same order as the
+/// asset table entries (i.e. `dependencies[entry.name_index]` belongs to
+/// `entry`, just like the name table).
+#[derive(Debug, Default)]
+struct DependencyTable {
+    dependencies: Vec<Vec<u64>>,
+}
+
+impl DependencyTable {
+    fn from_stream<T: Read>(mut stream: &mut T) -> Result<Self, io::Error> {
+        let header = DependencyTableHeader::from_stream(&mut stream)?;
+        let mut dependencies = Vec::with_capacity(bounded_capacity(header.num_assets));
         for _ in 0..header.num_assets {
-            let entry = AssetTableEntry::from_stream(&mut stream)?;
-            // TODO: Perform basic bounds checking here so it doesn't blow up later
-            asset_table.entries.insert(AssetId(entry.id), entry);
+            let num_deps = stream.read_u32::<LittleEndian>()?;
+            let mut ids = Vec::with_capacity(bounded_capacity(num_deps as u64));
+            for _ in 0..num_deps {
+                ids.push(stream.read_u64::<LittleEndian>()?);
+            }
+            dependencies.push(ids);
+        }
+
+        Ok(Self { dependencies })
+    }
+
+    fn to_stream<T: Write>(&self, stream: &mut T) -> Result<(), io::Error> {
+        for ids in &self.dependencies {
+            stream.write_u32::<LittleEndian>(ids.len() as u32)?;
+            for id in ids {
+                stream.write_u64::<LittleEndian>(*id)?;
+            }
         }
+        Ok(())
+    }
 
-        Ok(asset_table)
+    fn get_serialized_size(&self) -> usize {
+        self.dependencies.iter().map(|ids| 4 + 8 * ids.len()).sum()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct AssetId(u64);
+
+impl AssetId {
+    fn from_str(str: &str) -> Self {
+        let mut hasher = DefaultHasher::new();
+        str.hash(&mut hasher);
+        Self(hasher.finish())
     }
 }
 
 pub struct LibraryAssetIterator<'a> {
-    inner: hash_map::Values<'a, AssetId, AssetTableEntry>,
+    library: &'a Library,
+    next_index: u64,
 }
 
-impl<'a> Iterator for LibraryAssetIterator<'a> {
-    type Item = u64;
+impl Iterator for LibraryAssetIterator<'_> {
+    type Item = String;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.inner.next().map(|entry| entry.id)
+        if self.next_index >= self.library.num_entries() {
+            return None;
+        }
+
+        let index = self.next_index;
+        self.next_index += 1;
+
+        let entry = self.library.entry_at(index).ok()?;
+        self.library.name_at(entry.name_index).ok()
     }
 }
 
+/// A trait object bound so `Library::from_reader` can hold onto any
+/// `Read + Seek` source without making `Library` generic.
+trait ReadSeek: Read + Seek + Send {}
+impl<T: Read + Seek + Send> ReadSeek for T {}
+
+enum LibraryBacking {
+    // The fast, zero-allocation-at-open path: entries live directly in the
+    // mmap and are binary-searched in place.
+    Mapped {
+        source: Arc<AssetSource>,
+        // Byte offset of the first AssetTableEntry within `source`. Entries
+        // are sorted by id ascending, so lookups binary-search this region
+        // directly rather than materializing a HashMap at open time.
+        entries_offset: usize,
+        num_entries: u64,
+        // Byte offset of the (length-prefixed) name table data within `source`.
+        names_offset: usize,
+        // Byte offset of the (length-prefixed) dependency table data within `source`.
+        deps_offset: usize,
+    },
+    // The generic path for non-mmap-able sources: the table and names are
+    // parsed eagerly (there's no way to binary-search an arbitrary `Read`
+    // without buffering it), and each loaded asset seeks and reads its own
+    // bytes out of the shared reader on demand.
+    Streamed {
+        entries: Vec<AssetTableEntry>,
+        names: Vec<String>,
+        dependencies: Vec<Vec<u64>>,
+        reader: Mutex<Box<dyn ReadSeek>>,
+    },
+}
+
 pub struct Library {
-    source: Arc<AssetSource>,
-    assets: AssetTable,
+    backing: LibraryBacking,
 }
 
 impl Library {
+    /// Opens a library from an on-disk, mmap-able `File`. Like
+    /// [`Library::from_reader`], all offsets derived from the file's own
+    /// header fields are bounds-checked, so a truncated or corrupt file
+    /// returns an `io::Error` instead of panicking.
     pub fn new(file: &File) -> Result<Self, io::Error> {
         let source = unsafe { Mmap::map(file) }?;
         let mut data = source.deref();
@@ -144,14 +363,241 @@ impl Library {
         if file_header.magic_number != 0xdeadbeef_u64 {
             return Err(io::Error::from(io::ErrorKind::InvalidData));
         }
-        let assets = AssetTable::from_stream(&mut data)?;
-        let source = Arc::new(AssetSource { handle: source });
-        Ok(Self { source, assets })
+        if file_header.version != CURRENT_FORMAT_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "asset library version {} does not match the supported version {}",
+                    file_header.version, CURRENT_FORMAT_VERSION
+                ),
+            ));
+        }
+        let asset_table_header = AssetTableHeader::from_stream(&mut data)?;
+
+        let entries_offset =
+            FileHeader::get_serialized_size() + AssetTableHeader::get_serialized_size();
+        // All of the following is derived from attacker/corruption-controlled
+        // fields (num_assets, data_size), so every offset is computed with
+        // checked arithmetic and every slice is bounds-checked against the
+        // mapped region instead of indexed directly, to turn a truncated or
+        // corrupt file into an InvalidData error rather than a slice-index
+        // panic.
+        let entries_region_size = (asset_table_header.num_assets as usize)
+            .checked_mul(AssetTableEntry::get_serialized_size())
+            .ok_or_else(|| io::Error::from(io::ErrorKind::InvalidData))?;
+        let name_table_header_offset = entries_offset
+            .checked_add(entries_region_size)
+            .ok_or_else(|| io::Error::from(io::ErrorKind::InvalidData))?;
+        let name_table_header_bytes = source
+            .get(name_table_header_offset..)
+            .ok_or_else(|| io::Error::from(io::ErrorKind::InvalidData))?;
+        let name_table_header = NameTableHeader::from_stream(&mut &*name_table_header_bytes)?;
+        let names_offset = name_table_header_offset
+            .checked_add(NameTableHeader::get_serialized_size())
+            .ok_or_else(|| io::Error::from(io::ErrorKind::InvalidData))?;
+        let deps_offset = names_offset
+            .checked_add(name_table_header.data_size as usize)
+            .and_then(|offset| offset.checked_add(DependencyTableHeader::get_serialized_size()))
+            .ok_or_else(|| io::Error::from(io::ErrorKind::InvalidData))?;
+        if source.get(deps_offset..).is_none() {
+            return Err(io::Error::from(io::ErrorKind::InvalidData));
+        }
+
+        let source = Arc::new(AssetSource::Mmap(source));
+        Ok(Self {
+            backing: LibraryBacking::Mapped {
+                source,
+                entries_offset,
+                num_entries: asset_table_header.num_assets,
+                names_offset,
+                deps_offset,
+            },
+        })
+    }
+
+    /// Parses a library out of any `Read + Seek` source instead of requiring
+    /// an on-disk, mmap-able `File` — e.g. a library embedded inside another
+    /// archive, or received over a socket. The table and name data are
+    /// parsed up front since there's no mapped region to binary-search or
+    /// scan lazily; `load` seeks within the reader for each asset's bytes.
+    /// This and [`Library::new`] both reject a truncated/corrupt stream with
+    /// an `io::Error` rather than panicking, so either is safe to point at
+    /// untrusted input.
+    pub fn from_reader<R: Read + Seek + Send + 'static>(mut reader: R) -> Result<Self, io::Error> {
+        let file_header = FileHeader::from_stream(&mut reader)?;
+        if file_header.magic_number != 0xdeadbeef_u64 {
+            return Err(io::Error::from(io::ErrorKind::InvalidData));
+        }
+        if file_header.version != CURRENT_FORMAT_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "asset library version {} does not match the supported version {}",
+                    file_header.version, CURRENT_FORMAT_VERSION
+                ),
+            ));
+        }
+
+        let asset_table_header = AssetTableHeader::from_stream(&mut reader)?;
+        let mut entries = Vec::with_capacity(bounded_capacity(asset_table_header.num_assets));
+        for _ in 0..asset_table_header.num_assets {
+            entries.push(AssetTableEntry::from_stream(&mut reader)?);
+        }
+
+        let name_table = NameTable::from_stream(&mut reader)?;
+        let dependency_table = DependencyTable::from_stream(&mut reader)?;
+
+        Ok(Self {
+            backing: LibraryBacking::Streamed {
+                entries,
+                names: name_table.names,
+                dependencies: dependency_table.dependencies,
+                reader: Mutex::new(Box::new(reader)),
+            },
+        })
+    }
+
+    fn num_entries(&self) -> u64 {
+        match &self.backing {
+            LibraryBacking::Mapped { num_entries, .. } => *num_entries,
+            LibraryBacking::Streamed { entries, .. } => entries.len() as u64,
+        }
+    }
+
+    // Returns InvalidData rather than panicking when `index` is in-range but
+    // the offsets it implies fall outside the mapped/streamed data — this can
+    // happen on a truncated or corrupt file even though `num_entries` itself
+    // looked plausible at open time.
+    fn entry_at(&self, index: u64) -> Result<AssetTableEntry, io::Error> {
+        match &self.backing {
+            LibraryBacking::Mapped {
+                source,
+                entries_offset,
+                ..
+            } => {
+                let entry_size = AssetTableEntry::get_serialized_size();
+                let start = entries_offset
+                    .checked_add(
+                        (index as usize)
+                            .checked_mul(entry_size)
+                            .ok_or_else(|| io::Error::from(io::ErrorKind::InvalidData))?,
+                    )
+                    .ok_or_else(|| io::Error::from(io::ErrorKind::InvalidData))?;
+                let end = start
+                    .checked_add(entry_size)
+                    .ok_or_else(|| io::Error::from(io::ErrorKind::InvalidData))?;
+                let mut slice = source
+                    .get(start..end)
+                    .ok_or_else(|| io::Error::from(io::ErrorKind::InvalidData))?;
+                AssetTableEntry::from_stream(&mut slice)
+            }
+            LibraryBacking::Streamed { entries, .. } => {
+                let entry = entries
+                    .get(index as usize)
+                    .ok_or_else(|| io::Error::from(io::ErrorKind::InvalidData))?;
+                Ok(AssetTableEntry {
+                    id: entry.id,
+                    offset: entry.offset,
+                    size: entry.size,
+                    name_index: entry.name_index,
+                    stored_size: entry.stored_size,
+                    codec: entry.codec,
+                })
+            }
+        }
+    }
+
+    fn find_entry(&self, id: AssetId) -> Option<AssetTableEntry> {
+        let mut lo = 0u64;
+        let mut hi = self.num_entries();
+
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            let entry = self.entry_at(mid).ok()?;
+            match entry.id.cmp(&id.0) {
+                std::cmp::Ordering::Less => lo = mid + 1,
+                std::cmp::Ordering::Greater => hi = mid,
+                std::cmp::Ordering::Equal => return Some(entry),
+            }
+        }
+
+        None
+    }
+
+    fn name_at(&self, index: u32) -> Result<String, io::Error> {
+        match &self.backing {
+            LibraryBacking::Mapped {
+                source, names_offset, ..
+            } => {
+                let mut cursor = source
+                    .get(*names_offset..)
+                    .ok_or_else(|| io::Error::from(io::ErrorKind::InvalidData))?;
+                for _ in 0..index {
+                    let len = cursor.read_u32::<LittleEndian>()? as usize;
+                    cursor = cursor
+                        .get(len..)
+                        .ok_or_else(|| io::Error::from(io::ErrorKind::InvalidData))?;
+                }
+                let len = cursor.read_u32::<LittleEndian>()? as usize;
+                if len > cursor.len() {
+                    return Err(io::Error::from(io::ErrorKind::InvalidData));
+                }
+                let mut bytes = vec![0u8; len];
+                cursor.read_exact(&mut bytes)?;
+                String::from_utf8(bytes).map_err(|_| io::Error::from(io::ErrorKind::InvalidData))
+            }
+            LibraryBacking::Streamed { names, .. } => names
+                .get(index as usize)
+                .cloned()
+                .ok_or_else(|| io::Error::from(io::ErrorKind::InvalidData)),
+        }
+    }
+
+    /// Resolves the `index`'th entry's dependency ids into their stored
+    /// paths, looking each one up by binary search like a normal load.
+    ///
+    /// This is best-effort: a corrupt or truncated dependency section, or an
+    /// id that doesn't resolve to a known entry, just means that dependency
+    /// is missing from the result rather than failing the whole lookup. An
+    /// asset's dependency list degrading is preferable to making an
+    /// otherwise perfectly loadable asset unloadable because of an unrelated
+    /// bad edge in the dependency table.
+    fn dependency_paths_at(&self, index: u32) -> Vec<String> {
+        let ids: Vec<u64> = match &self.backing {
+            LibraryBacking::Mapped {
+                source, deps_offset, ..
+            } => (|| -> Option<Vec<u64>> {
+                let mut cursor = source.get(*deps_offset..)?;
+                for _ in 0..index {
+                    let num_deps = cursor.read_u32::<LittleEndian>().ok()?;
+                    let skip = (num_deps as usize).checked_mul(8)?;
+                    cursor = cursor.get(skip..)?;
+                }
+                let num_deps = cursor.read_u32::<LittleEndian>().ok()?;
+                let mut ids = Vec::with_capacity(std::cmp::min(num_deps as usize, cursor.len() / 8));
+                for _ in 0..num_deps {
+                    ids.push(cursor.read_u64::<LittleEndian>().ok()?);
+                }
+                Some(ids)
+            })()
+            .unwrap_or_default(),
+            LibraryBacking::Streamed { dependencies, .. } => {
+                dependencies.get(index as usize).cloned().unwrap_or_default()
+            }
+        };
+
+        ids.into_iter()
+            .filter_map(|id| {
+                let entry = self.find_entry(AssetId(id))?;
+                self.name_at(entry.name_index).ok()
+            })
+            .collect()
     }
 
     pub fn assets(&self) -> LibraryAssetIterator {
         LibraryAssetIterator {
-            inner: self.assets.entries.values(),
+            library: self,
+            next_index: 0,
         }
     }
 }
@@ -159,14 +605,33 @@ impl Library {
 #[derive(Clone)]
 pub struct AssetDescription {
     path: String,
+    codec: Codec,
+    dependencies: Vec<String>,
 }
 
 impl AssetDescription {
     pub fn new(path: &str) -> Self {
         Self {
             path: path.to_owned(),
+            codec: Codec::None,
+            dependencies: Vec::new(),
         }
     }
+
+    pub fn new_with_codec(path: &str, codec: Codec) -> Self {
+        Self {
+            path: path.to_owned(),
+            codec,
+            dependencies: Vec::new(),
+        }
+    }
+
+    /// Declares that this asset depends on the assets at `paths`, which
+    /// `AssetManager::load_with_dependencies` will load transitively
+    /// alongside it.
+    pub fn depends_on(&mut self, paths: &[&str]) {
+        self.dependencies = paths.iter().map(|path| (*path).to_owned()).collect();
+    }
 }
 
 pub struct Builder {
@@ -184,37 +649,162 @@ impl Builder {
         self.assets.insert(asset.path.clone(), asset.clone());
     }
 
+    fn compress(codec: Codec, bytes: &[u8]) -> Result<Vec<u8>, io::Error> {
+        match codec {
+            Codec::None => Ok(bytes.to_vec()),
+            Codec::Zstd => zstd::encode_all(bytes, 0),
+            Codec::Lz4 => lz4::block::compress(bytes, None, false),
+        }
+    }
+
     pub fn build<T: Write>(&self, mut output: &mut T) -> Result<(), io::Error> {
-        let mut asset_entries = Vec::new();
+        // Used to detect two different paths hashing to the same AssetId.
+        let mut seen_ids: HashMap<u64, String> = HashMap::with_capacity(self.assets.len());
+
+        // Pass 1: read every file once and compute its content hash, so
+        // byte-identical files (e.g. duplicated textures/icons) can share a
+        // single data region below instead of each getting their own copy.
+        struct PendingAsset {
+            path: String,
+            id: u64,
+            codec: Codec,
+            content_hash: blake3::Hash,
+            raw_bytes: Vec<u8>,
+            dependency_ids: Vec<u64>,
+        }
+
+        let mut pending = Vec::with_capacity(self.assets.len());
+        for desc in self.assets.values() {
+            let mut hasher = DefaultHasher::new();
+            desc.path.hash(&mut hasher);
+            let id = hasher.finish();
+
+            if let Some(existing_path) = seen_ids.get(&id) {
+                if existing_path != &desc.path {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!(
+                            "asset id collision between '{}' and '{}'",
+                            existing_path, desc.path
+                        ),
+                    ));
+                }
+            } else {
+                seen_ids.insert(id, desc.path.clone());
+            }
+
+            for dep_path in &desc.dependencies {
+                if !self.assets.contains_key(dep_path) {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!(
+                            "asset '{}' depends on '{}', which was never inserted into the builder",
+                            desc.path, dep_path
+                        ),
+                    ));
+                }
+            }
+
+            let raw_bytes = fs::read(&desc.path)?;
+            let content_hash = blake3::hash(&raw_bytes);
+            let dependency_ids = desc
+                .dependencies
+                .iter()
+                .map(|path| AssetId::from_str(path).0)
+                .collect();
+
+            pending.push(PendingAsset {
+                path: desc.path.clone(),
+                id,
+                codec: desc.codec,
+                content_hash,
+                raw_bytes,
+                dependency_ids,
+            });
+        }
 
         let asset_data_base_offset = (FileHeader::get_serialized_size()
             + AssetTableHeader::get_serialized_size()
-            + self.assets.len() * AssetTableEntry::get_serialized_size())
+            + pending.len() * AssetTableEntry::get_serialized_size()
+            + NameTableHeader::get_serialized_size()
+            + pending.iter().map(|p| 4 + p.path.len()).sum::<usize>()
+            + DependencyTableHeader::get_serialized_size()
+            + pending
+                .iter()
+                .map(|p| 4 + 8 * p.dependency_ids.len())
+                .sum::<usize>())
             as u64;
 
         let aligned_asset_data_base_offset = align_up(asset_data_base_offset, ASSET_ALIGN_SIZE);
 
-        let mut cur_asset_data_offset = aligned_asset_data_base_offset;
-
-        for desc in self.assets.values() {
-            let mut hasher = DefaultHasher::new();
-            desc.path.hash(&mut hasher);
-            let id = hasher.finish();
-
-            let offset = cur_asset_data_offset;
-            let size = fs::metadata(&desc.path).unwrap().len();
+        // Pass 2: assign each unique (content, codec) pair its own aligned
+        // data region, compressing it exactly once. Assets whose content
+        // hash and codec both match an already-placed region reuse that
+        // region's offset/size instead of being written again.
+        struct Region {
+            offset: u64,
+            size: u64,
+            stored_size: u64,
+        }
 
-            // Ensure that new asset offsets always begin at an aligned address
-            cur_asset_data_offset += align_up(size, ASSET_ALIGN_SIZE);
+        let mut regions: HashMap<(blake3::Hash, Codec), Region> = HashMap::new();
+        let mut unique_stored_data: Vec<Vec<u8>> = Vec::new();
+        let mut cur_asset_data_offset = aligned_asset_data_base_offset;
 
-            let entry = AssetTableEntry { id, offset, size };
+        let mut entries_with_data: Vec<(AssetTableEntry, String, Vec<u64>)> =
+            Vec::with_capacity(pending.len());
+        for asset in &pending {
+            let region_key = (asset.content_hash, asset.codec);
+            let region = if let Some(region) = regions.get(&region_key) {
+                region
+            } else {
+                let stored_bytes = Self::compress(asset.codec, &asset.raw_bytes)?;
+                let region = Region {
+                    offset: cur_asset_data_offset,
+                    size: asset.raw_bytes.len() as u64,
+                    stored_size: stored_bytes.len() as u64,
+                };
+
+                // Ensure that new data regions always begin at an aligned address
+                cur_asset_data_offset += align_up(region.stored_size, ASSET_ALIGN_SIZE);
+
+                unique_stored_data.push(stored_bytes);
+                &*regions.entry(region_key).or_insert(region)
+            };
+
+            // name_index is filled in below, once the entries are sorted.
+            let entry = AssetTableEntry {
+                id: asset.id,
+                offset: region.offset,
+                size: region.size,
+                name_index: 0,
+                stored_size: region.stored_size,
+                codec: asset.codec as u8,
+            };
+
+            entries_with_data.push((entry, asset.path.clone(), asset.dependency_ids.clone()));
+        }
 
+        // Sort entries by id ascending so `Library` can binary-search the
+        // on-disk table instead of reading it all into a HashMap at open
+        // time. The name table and dependency table are written in the same
+        // order, so name_index is just each entry's final position.
+        entries_with_data.sort_by_key(|(entry, _, _)| entry.id);
+
+        let mut name_table = NameTable::default();
+        let mut dependency_table = DependencyTable::default();
+        let mut asset_entries = Vec::with_capacity(entries_with_data.len());
+        for (index, (mut entry, path, dependency_ids)) in entries_with_data.into_iter().enumerate() {
+            entry.name_index = index as u32;
+            name_table.names.push(path);
+            dependency_table.dependencies.push(dependency_ids);
             asset_entries.push(entry);
         }
 
         // Write the file header
         let file_header = FileHeader {
             magic_number: 0xdeadbeef_u64,
+            version: CURRENT_FORMAT_VERSION,
         };
         file_header.to_stream(&mut output)?;
 
@@ -229,22 +819,36 @@ impl Builder {
             entry.to_stream(&mut output)?;
         }
 
+        // Write the name table
+        let name_table_header = NameTableHeader {
+            num_names: name_table.names.len() as u64,
+            data_size: name_table.get_serialized_size() as u64,
+        };
+        name_table_header.to_stream(&mut output)?;
+        name_table.to_stream(&mut output)?;
+
+        // Write the dependency table
+        let dependency_table_header = DependencyTableHeader {
+            num_assets: dependency_table.dependencies.len() as u64,
+        };
+        dependency_table_header.to_stream(&mut output)?;
+        dependency_table.to_stream(&mut output)?;
+
         // Write padding bytes before the assets
         let padding_bytes = aligned_asset_data_base_offset - asset_data_base_offset;
         for _ in 0..padding_bytes {
             output.write_u8(0)?;
         }
 
-        // Write the asset data
-        for desc in self.assets.values() {
-            let mut file = File::open(&desc.path)?;
-            let mut bytes_copied = io::copy(&mut file, &mut output)?;
+        // Write the asset data (only unique regions; duplicate content is
+        // never written twice)
+        for bytes in &unique_stored_data {
+            output.write_all(bytes)?;
 
             // Write padding bytes until we hit the required alignment for asset data
-            let aligned_size = align_up(bytes_copied, ASSET_ALIGN_SIZE);
-            while bytes_copied != aligned_size {
+            let aligned_size = align_up(bytes.len() as u64, ASSET_ALIGN_SIZE);
+            for _ in bytes.len() as u64..aligned_size {
                 output.write_u8(0)?;
-                bytes_copied += 1;
             }
         }
 
@@ -262,7 +866,10 @@ pub struct Asset {
     path: String,
     offset: u64,
     size: u64,
+    stored_size: u64,
+    codec: Codec,
     source: Arc<AssetSource>,
+    dependencies: Vec<String>,
 }
 
 impl Asset {
@@ -270,13 +877,63 @@ impl Asset {
         &self.path
     }
 
+    /// Paths of the assets this one declared as dependencies at build time,
+    /// via [`AssetDescription::depends_on`].
+    pub fn dependencies(&self) -> &[String] {
+        &self.dependencies
+    }
+
+    /// The raw bytes backing this asset, exactly as they're stored on disk.
+    /// When the asset was compressed, this is the compressed stream; use
+    /// [`Asset::read`] to get the decompressed content instead.
     pub fn data(&self) -> &[u8] {
-        &(&self.source.handle)[(self.offset as usize)..((self.offset + self.size) as usize)]
+        &self.source[(self.offset as usize)..((self.offset + self.stored_size) as usize)]
+    }
+
+    /// Returns the decompressed contents of this asset, allocating a new
+    /// buffer. Falls back to a plain copy of [`Asset::data`] when the asset
+    /// isn't compressed.
+    pub fn read(&self) -> Result<Vec<u8>, io::Error> {
+        match self.codec {
+            Codec::None => Ok(self.data().to_vec()),
+            Codec::Zstd => zstd::decode_all(self.data()),
+            Codec::Lz4 => {
+                // lz4::block::decompress takes the expected output size as an
+                // i32, so an asset whose uncompressed size doesn't fit can't
+                // be round-tripped through this codec.
+                let size = i32::try_from(self.size).map_err(|_| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!(
+                            "asset is too large to decompress with Lz4: {} bytes exceeds the i32::MAX size limit",
+                            self.size
+                        ),
+                    )
+                })?;
+                lz4::block::decompress(self.data(), Some(size))
+            }
+        }
     }
 }
 
-struct AssetSource {
-    handle: Mmap,
+/// Where an [`Asset`]'s bytes physically live. `Mmap` is the fast, zero-copy
+/// default for on-disk libraries; `Owned` backs assets read through
+/// [`Library::from_reader`], where each asset carries its own buffer instead
+/// of sharing one big mapping.
+enum AssetSource {
+    Mmap(Mmap),
+    Owned(Box<[u8]>),
+}
+
+impl Deref for AssetSource {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            AssetSource::Mmap(mmap) => mmap,
+            AssetSource::Owned(bytes) => bytes,
+        }
+    }
 }
 
 pub trait AssetLoader {
@@ -306,12 +963,15 @@ impl AssetLoader for FileAssetLoader {
                 let path = path.to_owned();
                 let offset = 0;
                 let size = mmap.len() as u64;
-                let source = Arc::new(AssetSource { handle: mmap });
+                let source = Arc::new(AssetSource::Mmap(mmap));
                 let asset = Arc::new(Asset {
                     path,
                     offset,
                     size,
+                    stored_size: size,
+                    codec: Codec::None,
                     source,
+                    dependencies: Vec::new(),
                 });
 
                 result = Some(asset);
@@ -324,23 +984,61 @@ impl AssetLoader for FileAssetLoader {
 
 impl AssetLoader for Library {
     fn load(&self, path: &str) -> Option<Arc<Asset>> {
-        let mut result = None;
-
-        if let Some(entry) = self.assets.entries.get(&AssetId::from_str(path)) {
-            let path = path.to_owned();
-            let offset = entry.offset;
-            let size = entry.size;
-            let asset = Arc::new(Asset {
-                path,
-                offset,
-                size,
-                source: self.source.clone(),
-            });
-
-            result = Some(asset);
+        let id = AssetId::from_str(path);
+        let entry = self.find_entry(id)?;
+
+        // The canonical path is whatever was stored in the name table at
+        // build time, not necessarily the string the caller looked it up
+        // with.
+        let path = self.name_at(entry.name_index).ok()?;
+        let codec = Codec::from_u8(entry.codec).ok()?;
+        let dependencies = self.dependency_paths_at(entry.name_index);
+
+        match &self.backing {
+            LibraryBacking::Mapped { source, .. } => {
+                // The entry's range comes straight from the file, so check it
+                // against the mapping before `Asset::data` slices into it.
+                let end = entry.offset.checked_add(entry.stored_size)?;
+                if end > source.len() as u64 {
+                    return None;
+                }
+
+                Some(Arc::new(Asset {
+                    path,
+                    offset: entry.offset,
+                    size: entry.size,
+                    stored_size: entry.stored_size,
+                    codec,
+                    source: source.clone(),
+                    dependencies,
+                }))
+            }
+            LibraryBacking::Streamed { reader, .. } => {
+                // Read through `take` rather than pre-sizing a buffer from
+                // `stored_size`, so a corrupt size can't force a huge
+                // allocation; a short read means the stream is truncated.
+                let mut bytes = Vec::new();
+                let mut reader = reader.lock().unwrap();
+                reader.seek(SeekFrom::Start(entry.offset)).ok()?;
+                (&mut *reader)
+                    .take(entry.stored_size)
+                    .read_to_end(&mut bytes)
+                    .ok()?;
+                if bytes.len() as u64 != entry.stored_size {
+                    return None;
+                }
+
+                Some(Arc::new(Asset {
+                    path,
+                    offset: 0,
+                    size: entry.size,
+                    stored_size: entry.stored_size,
+                    codec,
+                    source: Arc::new(AssetSource::Owned(bytes.into_boxed_slice())),
+                    dependencies,
+                }))
+            }
         }
-
-        result
     }
 }
 
@@ -368,6 +1066,29 @@ impl<T: AssetLoader> AssetManager<T> {
 
         result
     }
+
+    /// Loads the asset at `path`, then transitively loads every asset it
+    /// (directly or indirectly) depends on, populating the cache with the
+    /// whole reachable closure. Safe against dependency cycles.
+    pub fn load_with_dependencies(&mut self, path: &str) -> Option<Arc<Asset>> {
+        let root = self.load(path)?;
+
+        let mut visited = HashSet::new();
+        visited.insert(path.to_owned());
+
+        let mut pending: Vec<String> = root.dependencies().to_vec();
+        while let Some(dep_path) = pending.pop() {
+            if !visited.insert(dep_path.clone()) {
+                continue;
+            }
+
+            if let Some(asset) = self.load(&dep_path) {
+                pending.extend(asset.dependencies().iter().cloned());
+            }
+        }
+
+        Some(root)
+    }
 }
 
 #[cfg(test)]
@@ -435,8 +1156,226 @@ mod tests {
             let asset = mgr.load(path).unwrap();
             let file_data = fs::read(path).unwrap();
             assert_eq!(asset.data(), &file_data);
+            assert_eq!(asset.path(), path);
         }
 
         Ok(())
     }
+
+    #[test]
+    fn library_assets_yields_real_paths() -> Result<(), io::Error> {
+        let asset_paths = get_test_asset_paths();
+
+        let mut builder = Builder::new();
+        for path in &asset_paths {
+            builder.insert(&AssetDescription::new(path));
+        }
+
+        let mut file = tempfile::tempfile()?;
+        builder.build(&mut file)?;
+        file.rewind()?;
+
+        let library = Library::new(&file)?;
+
+        let mut found: Vec<String> = library.assets().collect();
+        found.sort();
+
+        let mut expected = asset_paths;
+        expected.sort();
+
+        assert_eq!(found, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn library_compressed_asset_roundtrips() -> Result<(), io::Error> {
+        let asset_paths = get_test_asset_paths();
+
+        let mut builder = Builder::new();
+        for path in &asset_paths {
+            builder.insert(&AssetDescription::new_with_codec(path, Codec::Zstd));
+        }
+
+        let mut file = tempfile::tempfile()?;
+        builder.build(&mut file)?;
+        file.rewind()?;
+
+        let library = Library::new(&file)?;
+        let mut mgr = AssetManager::<Library>::new(library);
+
+        for path in &asset_paths {
+            let asset = mgr.load(path).unwrap();
+            let file_data = fs::read(path).unwrap();
+            assert_eq!(asset.read()?, file_data);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn library_from_reader_matches_mmap() -> Result<(), io::Error> {
+        let asset_paths = get_test_asset_paths();
+
+        let mut builder = Builder::new();
+        for path in &asset_paths {
+            builder.insert(&AssetDescription::new(path));
+        }
+
+        let mut file = tempfile::tempfile()?;
+        builder.build(&mut file)?;
+        file.rewind()?;
+
+        let library = Library::from_reader(file)?;
+        let mut mgr = AssetManager::<Library>::new(library);
+
+        for path in &asset_paths {
+            let asset = mgr.load(path).unwrap();
+            let file_data = fs::read(path).unwrap();
+            assert_eq!(asset.data(), &file_data);
+            assert_eq!(asset.path(), path);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn library_from_reader_rejects_absurd_asset_count_without_panicking() {
+        let mut bytes = Vec::new();
+        bytes.write_u64::<LittleEndian>(0xdeadbeef_u64).unwrap();
+        bytes.write_u32::<LittleEndian>(CURRENT_FORMAT_VERSION).unwrap();
+        // A claimed asset count near u64::MAX, with none of the entry data
+        // actually present. Pre-sizing a Vec directly from this count used
+        // to abort the process with a capacity overflow instead of failing
+        // gracefully.
+        bytes.write_u64::<LittleEndian>(u64::MAX).unwrap();
+
+        let cursor = io::Cursor::new(bytes);
+        let result = Library::from_reader(cursor);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn library_from_reader_rejects_absurd_dependency_count_without_panicking() {
+        let mut bytes = Vec::new();
+        bytes.write_u64::<LittleEndian>(0xdeadbeef_u64).unwrap();
+        bytes.write_u32::<LittleEndian>(CURRENT_FORMAT_VERSION).unwrap();
+        bytes.write_u64::<LittleEndian>(0).unwrap(); // asset table: no assets
+        bytes.write_u64::<LittleEndian>(0).unwrap(); // name table: no names
+        bytes.write_u64::<LittleEndian>(0).unwrap(); // name table: no data
+        // A claimed dependency-table asset count near u64::MAX, with none of
+        // the dependency data actually present.
+        bytes.write_u64::<LittleEndian>(u64::MAX).unwrap();
+
+        let cursor = io::Cursor::new(bytes);
+        let result = Library::from_reader(cursor);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn library_dedups_identical_content() -> Result<(), io::Error> {
+        // test1.txt and test2.txt happen to share no content in this fixture
+        // set, but test0.txt inserted under two different logical paths
+        // exercises the same content-dedup code path: both entries should
+        // still resolve independently to the same underlying bytes.
+        let original_path = make_asset_path("test0.txt");
+        let aliased_path = make_asset_path("../testing/test0.txt");
+
+        let mut builder = Builder::new();
+        builder.insert(&AssetDescription::new(&original_path));
+        builder.insert(&AssetDescription::new(&aliased_path));
+
+        let mut file = tempfile::tempfile()?;
+        builder.build(&mut file)?;
+        file.rewind()?;
+
+        let library = Library::new(&file)?;
+        let mut mgr = AssetManager::<Library>::new(library);
+
+        let file_data = fs::read(&original_path).unwrap();
+        let original = mgr.load(&original_path).unwrap();
+        let aliased = mgr.load(&aliased_path).unwrap();
+        assert_eq!(original.data(), &file_data);
+        assert_eq!(aliased.data(), &file_data);
+        // Both entries address the one shared data region.
+        assert_eq!(original.data().as_ptr(), aliased.data().as_ptr());
+
+        Ok(())
+    }
+
+    #[test]
+    fn load_with_dependencies_pulls_in_the_whole_closure() -> Result<(), io::Error> {
+        let asset_paths = get_test_asset_paths();
+        let root_path = &asset_paths[0];
+        let dep_paths = &asset_paths[1..];
+
+        let mut root_desc = AssetDescription::new(root_path);
+        root_desc.depends_on(&dep_paths.iter().map(String::as_str).collect::<Vec<_>>());
+
+        let mut builder = Builder::new();
+        builder.insert(&root_desc);
+        for path in dep_paths {
+            builder.insert(&AssetDescription::new(path));
+        }
+
+        let mut file = tempfile::tempfile()?;
+        builder.build(&mut file)?;
+        file.rewind()?;
+
+        let library = Library::new(&file)?;
+        let mut mgr = AssetManager::<Library>::new(library);
+
+        let root = mgr.load_with_dependencies(root_path).unwrap();
+        assert_eq!(root.dependencies(), dep_paths);
+
+        for path in dep_paths {
+            assert!(mgr.assets.contains_key(path));
+            let file_data = fs::read(path).unwrap();
+            assert_eq!(mgr.load(path).unwrap().data(), &file_data);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn load_with_dependencies_is_cycle_safe() -> Result<(), io::Error> {
+        let asset_paths = get_test_asset_paths();
+        let (a_path, b_path) = (&asset_paths[0], &asset_paths[1]);
+
+        let mut a_desc = AssetDescription::new(a_path);
+        a_desc.depends_on(&[b_path]);
+
+        let mut b_desc = AssetDescription::new(b_path);
+        b_desc.depends_on(&[a_path]);
+
+        let mut builder = Builder::new();
+        builder.insert(&a_desc);
+        builder.insert(&b_desc);
+
+        let mut file = tempfile::tempfile()?;
+        builder.build(&mut file)?;
+        file.rewind()?;
+
+        let library = Library::new(&file)?;
+        let mut mgr = AssetManager::<Library>::new(library);
+
+        let root = mgr.load_with_dependencies(a_path).unwrap();
+        assert_eq!(root.path(), a_path);
+
+        Ok(())
+    }
+
+    #[test]
+    fn build_rejects_a_dependency_on_an_unknown_asset() {
+        let asset_paths = get_test_asset_paths();
+
+        let mut desc = AssetDescription::new(&asset_paths[0]);
+        desc.depends_on(&["this/path/was/never/inserted.png"]);
+
+        let mut builder = Builder::new();
+        builder.insert(&desc);
+
+        let mut file = Vec::new();
+        assert!(builder.build(&mut file).is_err());
+    }
 }